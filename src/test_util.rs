@@ -0,0 +1,205 @@
+//! An in-memory fake of the Instapaper API, to test code using the `Api` trait without network
+//! access. Requires the `test-util` feature.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use {Api, ApiError, Bookmark, Folder, FolderId, List, Result, User};
+
+/// Implements `Api` on bookmarks and folders kept in memory. `add()` saves bookmarks to `unread`,
+/// `archive()` moves them to `archive`, and `delete()` removes them. Use `fail_next()` to make the
+/// next call return an error instead.
+///
+/// ```
+/// use instapaper::test_util::MockClient;
+/// use instapaper::{Api, FolderId};
+///
+/// fn save_and_archive<A: Api>(api: &A, url: &str) -> instapaper::Result<()> {
+///     let bookmark = api.add(url, "", "")?;
+///     api.archive(bookmark.bookmark_id)?;
+///     Ok(())
+/// }
+///
+/// let mock = MockClient::new();
+/// save_and_archive(&mock, "https://sirupsen.com/read").unwrap();
+/// assert!(mock.bookmarks().unwrap().bookmarks.is_empty());
+/// assert_eq!(1, mock.bookmarks_in(FolderId::Archive).unwrap().bookmarks.len());
+/// ```
+#[derive(Debug, Default)]
+pub struct MockClient {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    user: User,
+    bookmarks: Vec<(FolderId, Bookmark)>,
+    folders: Vec<Folder>,
+    failures: VecDeque<ApiError>,
+    next_id: i64,
+}
+
+impl MockClient {
+    pub fn new() -> MockClient {
+        MockClient::default()
+    }
+
+    /// The user returned by `verify()`.
+    pub fn with_user(self, user: User) -> MockClient {
+        self.state.lock().unwrap().user = user;
+        self
+    }
+
+    /// Store `bookmark` in `folder`, as if it had been saved before.
+    pub fn with_bookmark(self, folder: impl Into<FolderId>, bookmark: Bookmark) -> MockClient {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.next_id = state.next_id.max(bookmark.bookmark_id);
+            state.bookmarks.push((folder.into(), bookmark));
+        }
+        self
+    }
+
+    /// A folder returned by `folders()`.
+    pub fn with_folder(self, folder: Folder) -> MockClient {
+        self.state.lock().unwrap().folders.push(folder);
+        self
+    }
+
+    /// Make the next call fail with `error`. Queued failures are returned in order.
+    pub fn fail_next(&self, error: ApiError) {
+        self.state.lock().unwrap().failures.push_back(error);
+    }
+
+    fn state(&self) -> Result<::std::sync::MutexGuard<'_, State>> {
+        let mut state = self.state.lock().unwrap();
+        let failure = state.failures.pop_front();
+        match failure {
+            Some(error) => Err(error.into()),
+            None => Ok(state),
+        }
+    }
+
+    fn move_to(&self, bookmark_id: i64, folder: FolderId) -> Result<Bookmark> {
+        let mut state = self.state()?;
+        match state.bookmarks.iter_mut().find(|entry| entry.1.bookmark_id == bookmark_id) {
+            Some(entry) => {
+                entry.0 = folder;
+                Ok(entry.1.clone())
+            }
+            None => Err(invalid_bookmark_id()),
+        }
+    }
+}
+
+fn invalid_bookmark_id() -> ::failure::Error {
+    ApiError::Api {
+        code: ::ApiErrorCode::InvalidBookmarkId,
+        message: "Invalid or missing bookmark_id".to_string(),
+    }.into()
+}
+
+impl Api for MockClient {
+    fn verify(&self) -> Result<User> {
+        Ok(self.state()?.user.clone())
+    }
+
+    fn bookmarks_in(&self, folder: FolderId) -> Result<List> {
+        let state = self.state()?;
+        Ok(List {
+            bookmarks: state
+                .bookmarks
+                .iter()
+                .filter(|entry| entry.0 == folder)
+                .map(|entry| entry.1.clone())
+                .collect(),
+            user: state.user.clone(),
+            folder: Some(folder),
+            ..List::default()
+        })
+    }
+
+    fn add(&self, url: &str, title: &str, description: &str) -> Result<Bookmark> {
+        let mut state = self.state()?;
+        if let Some(entry) = state.bookmarks.iter().find(|entry| entry.1.url == url) {
+            return Ok(entry.1.clone());
+        }
+
+        state.next_id += 1;
+        let bookmark = Bookmark {
+            bookmark_id: state.next_id,
+            url: url.to_owned(),
+            title: title.to_owned(),
+            description: description.to_owned(),
+            starred: "0".to_string(),
+            kind: "bookmark".to_string(),
+            ..Bookmark::default()
+        };
+        state.bookmarks.push((FolderId::Unread, bookmark.clone()));
+        Ok(bookmark)
+    }
+
+    fn archive(&self, bookmark_id: i64) -> Result<Bookmark> {
+        self.move_to(bookmark_id, FolderId::Archive)
+    }
+
+    fn delete(&self, bookmark_id: i64) -> Result<()> {
+        let mut state = self.state()?;
+        let before = state.bookmarks.len();
+        state.bookmarks.retain(|entry| entry.1.bookmark_id != bookmark_id);
+        if state.bookmarks.len() == before {
+            return Err(invalid_bookmark_id());
+        }
+        Ok(())
+    }
+
+    fn folders(&self) -> Result<Vec<Folder>> {
+        Ok(self.state()?.folders.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_client_add_archive_delete() {
+        let mock = MockClient::new().with_bookmark(
+            FolderId::Unread,
+            Bookmark {
+                bookmark_id: 10,
+                url: "https://sirupsen.com/napkin".to_string(),
+                ..Bookmark::default()
+            },
+        );
+
+        let bookmark = mock.add("https://sirupsen.com/read", "How I Read", "").unwrap();
+        assert_eq!(11, bookmark.bookmark_id);
+        assert_eq!(bookmark, mock.add("https://sirupsen.com/read", "", "").unwrap());
+        assert_eq!(2, mock.bookmarks().unwrap().bookmarks.len());
+
+        mock.archive(11).unwrap();
+        assert_eq!(1, mock.bookmarks().unwrap().bookmarks.len());
+        assert_eq!(11, mock.bookmarks_in(FolderId::Archive).unwrap().bookmarks[0].bookmark_id);
+
+        mock.delete(11).unwrap();
+        assert!(mock.bookmarks_in(FolderId::Archive).unwrap().bookmarks.is_empty());
+        assert!(mock.delete(11).is_err(), "Expected an error deleting a missing bookmark");
+    }
+
+    #[test]
+    fn test_mock_client_fail_next() {
+        let mock = MockClient::new().with_user(User {
+            user_id: 42,
+            ..User::default()
+        });
+
+        mock.fail_next(ApiError::Status {
+            status: 503,
+            body: String::new(),
+            retry_after: None,
+        });
+        assert!(mock.verify().is_err(), "Expected the queued failure");
+        assert_eq!(42, mock.verify().unwrap().user_id);
+    }
+}
@@ -0,0 +1,84 @@
+//! Tests against the live Instapaper API, to catch changes to the API that the mocked tests can't.
+//! They only run with the `live-tests` feature:
+//!
+//! ```sh
+//! cargo test --features live-tests
+//! ```
+//!
+//! The account to test with is read from the environment, or a `.env` file:
+//!
+//! * `INSTAPAPER_USERNAME`
+//! * `INSTAPAPER_PASSWORD`
+//! * `INSTAPAPER_CONSUMER_KEY`
+//! * `INSTAPAPER_CONSUMER_SECRET`
+//!
+//! The tests are skipped if any of them are missing. Anything the tests add to the account is
+//! deleted again.
+#![cfg(feature = "live-tests")]
+
+extern crate dotenv;
+extern crate instapaper;
+
+use std::env;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A URL unique to this run. Instapaper dedupes adds by URL, so adding a URL the account already
+/// has saved would return, and the test then delete, the user's own bookmark.
+fn unique_url() -> String {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    format!("https://sirupsen.com/read?instapaper-rs-live-test={}", nanos)
+}
+
+fn client() -> Option<instapaper::Client> {
+    dotenv::dotenv().ok();
+
+    let var = |name: &str| env::var(name).ok();
+    match (
+        var("INSTAPAPER_USERNAME"),
+        var("INSTAPAPER_PASSWORD"),
+        var("INSTAPAPER_CONSUMER_KEY"),
+        var("INSTAPAPER_CONSUMER_SECRET"),
+    ) {
+        (Some(username), Some(password), Some(key), Some(secret)) => Some(
+            instapaper::authenticate(&username, &password, &key, &secret)
+                .expect("failed to authenticate"),
+        ),
+        _ => {
+            eprintln!("INSTAPAPER_* environment variables missing, skipping live test");
+            None
+        }
+    }
+}
+
+#[test]
+fn test_live_verify() {
+    let client = match client() {
+        Some(client) => client,
+        None => return,
+    };
+
+    let user = client.verify().expect("failed to verify credentials");
+    assert!(user.user_id > 0);
+}
+
+#[test]
+fn test_live_add_list_delete() {
+    let client = match client() {
+        Some(client) => client,
+        None => return,
+    };
+
+    let url = unique_url();
+    let bookmark = client
+        .add(&url, "instapaper-rs live test", "")
+        .expect("failed to add bookmark");
+
+    // Delete before asserting, so a failed assertion doesn't leave the bookmark behind.
+    let listed = client
+        .bookmarks()
+        .map(|list| list.bookmarks.iter().any(|b| b.bookmark_id == bookmark.bookmark_id));
+    client.delete(bookmark.bookmark_id).expect("failed to delete bookmark");
+
+    assert_eq!(url, bookmark.url);
+    assert!(listed.expect("failed to list bookmarks"), "Expected the added bookmark in the list");
+}